@@ -0,0 +1,81 @@
+//! Indirection over time sources.
+//!
+//! `Timer`, `Meter` and friends read "now" through this module instead of
+//! calling `Instant::now()`/`SystemTime::now()` directly. In normal builds
+//! that's exactly what these functions do. Under the `mock_clock` feature
+//! they instead read from a thread-safe global that tests can move forward
+//! with `advance`, which makes rate decay and latency windows deterministic
+//! to test.
+
+#[cfg(not(feature = "mock_clock"))]
+use std::time::{Instant, SystemTime};
+
+#[cfg(not(feature = "mock_clock"))]
+pub fn now() -> Instant {
+    Instant::now()
+}
+
+#[cfg(not(feature = "mock_clock"))]
+pub fn system_now() -> SystemTime {
+    SystemTime::now()
+}
+
+#[cfg(feature = "mock_clock")]
+mod mock {
+    use std::sync::Mutex;
+    use std::time::{Duration, Instant, SystemTime};
+
+    use once_cell::sync::Lazy;
+
+    struct MockClock {
+        instant_base: Instant,
+        system_base: SystemTime,
+        offset: Duration,
+    }
+
+    static CLOCK: Lazy<Mutex<MockClock>> = Lazy::new(|| {
+        Mutex::new(MockClock {
+            instant_base: Instant::now(),
+            system_base: SystemTime::now(),
+            offset: Duration::from_secs(0),
+        })
+    });
+
+    pub fn now() -> Instant {
+        let clock = CLOCK.lock().unwrap();
+        clock.instant_base + clock.offset
+    }
+
+    pub fn system_now() -> SystemTime {
+        let clock = CLOCK.lock().unwrap();
+        clock.system_base + clock.offset
+    }
+
+    /// Move the mock clock forward. Affects both `now()` and `system_now()`.
+    pub fn advance(duration: Duration) {
+        let mut clock = CLOCK.lock().unwrap();
+        clock.offset += duration;
+    }
+}
+
+#[cfg(feature = "mock_clock")]
+pub use mock::{advance, now, system_now};
+
+#[cfg(feature = "mock_clock")]
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::{advance, now, system_now};
+
+    #[test]
+    fn test_advance_moves_now_and_system_now() {
+        let before = now();
+        let sys_before = system_now();
+
+        advance(Duration::from_secs(10));
+
+        assert!(now() >= before + Duration::from_secs(10));
+        assert!(system_now() >= sys_before + Duration::from_secs(10));
+    }
+}
@@ -0,0 +1,136 @@
+//! A lightweight pull-style HTTP endpoint exposing `MetricsRegistry`
+//! snapshots, for scrapers that don't want to wire up their own web
+//! framework. Requires the `http_pull` feature (which implies `ser`).
+
+use std::io::Error;
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+use tiny_http::{Method, Response, Server};
+
+use crate::registry::MetricsRegistry;
+
+/// A handle to a running `serve` server. Call `stop` to shut it down and
+/// wait for its background thread to exit.
+pub struct ServerHandle {
+    server: Arc<Server>,
+    join_handle: Option<JoinHandle<()>>,
+}
+
+impl ServerHandle {
+    /// The address the server actually bound to — handy when `serve` was
+    /// given port 0 and the caller needs to know which port was picked.
+    pub fn addr(&self) -> std::net::SocketAddr {
+        match self.server.server_addr() {
+            tiny_http::ListenAddr::IP(addr) => addr,
+            _ => panic!("serve() only binds TCP addresses, not unix sockets"),
+        }
+    }
+
+    pub fn stop(mut self) {
+        self.server.unblock();
+        if let Some(join_handle) = self.join_handle.take() {
+            let _ = join_handle.join();
+        }
+    }
+}
+
+/// Spawn a background thread that serves `GET <path>` on `addr` with
+/// `registry.snapshots()` rendered as JSON.
+pub fn serve(registry: Arc<MetricsRegistry>, addr: &str, path: &str) -> std::io::Result<ServerHandle> {
+    let server = Arc::new(Server::http(addr).map_err(|err| Error::other(err.to_string()))?);
+    let path = path.to_string();
+    let server_for_thread = server.clone();
+
+    let join_handle = thread::spawn(move || {
+        for request in server_for_thread.incoming_requests() {
+            if request.method() != &Method::Get || request.url() != path {
+                let _ = request.respond(Response::empty(404));
+                continue;
+            }
+
+            match serde_json::to_string(&registry.snapshots()) {
+                Ok(body) => {
+                    let response = Response::from_string(body).with_header(
+                        "Content-Type: application/json"
+                            .parse::<tiny_http::Header>()
+                            .unwrap(),
+                    );
+                    let _ = request.respond(response);
+                }
+                Err(_) => {
+                    let _ = request.respond(Response::empty(500));
+                }
+            }
+        }
+    });
+
+    Ok(ServerHandle {
+        server,
+        join_handle: Some(join_handle),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{Read, Write};
+    use std::net::{SocketAddr, TcpStream};
+    use std::time::{Instant, SystemTime};
+
+    use super::serve;
+    use crate::registry::MetricsRegistry;
+
+    /// Issue a bare-bones HTTP/1.1 GET over a raw `TcpStream` and return
+    /// `(status_code, body)`. Avoids pulling in an HTTP client crate just
+    /// for tests of the `http_pull` feature.
+    fn get(addr: SocketAddr, path: &str) -> (u16, String) {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(
+            stream,
+            "GET {path} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n"
+        )
+        .unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        let mut parts = response.splitn(2, "\r\n\r\n");
+        let head = parts.next().unwrap_or("");
+        let body = parts.next().unwrap_or("").to_string();
+
+        let status = head
+            .lines()
+            .next()
+            .and_then(|line| line.split_whitespace().nth(1))
+            .and_then(|code| code.parse().ok())
+            .unwrap_or(0);
+
+        (status, body)
+    }
+
+    #[test]
+    fn test_serve_responds_with_registry_snapshot_json() {
+        let registry = MetricsRegistry::arc();
+        registry.meter("hello", SystemTime::now()).mark(Instant::now());
+
+        let handle = serve(registry, "127.0.0.1:0", "/metrics").unwrap();
+        let (status, body) = get(handle.addr(), "/metrics");
+
+        assert_eq!(status, 200);
+        assert!(body.contains("\"count\":1"));
+
+        handle.stop();
+    }
+
+    #[test]
+    fn test_serve_returns_404_for_unknown_path() {
+        let registry = MetricsRegistry::arc();
+
+        let handle = serve(registry, "127.0.0.1:0", "/metrics").unwrap();
+        let (status, _) = get(handle.addr(), "/not-metrics");
+
+        assert_eq!(status, 404);
+
+        handle.stop();
+    }
+}
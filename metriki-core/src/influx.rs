@@ -0,0 +1,209 @@
+//! Periodically drains `MetricsRegistry` snapshots and reports them to
+//! InfluxDB using the line protocol. Requires the `influx` feature.
+//! Only `Timer` metrics are written; meters and EWMA latencies are skipped.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, UNIX_EPOCH};
+
+use crate::clock;
+use crate::registry::{MetricKind, MetricSnapshot, MetricsRegistry};
+
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(60);
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Builds an `InfluxReporter`.
+pub struct InfluxReporterBuilder {
+    url: String,
+    interval: Duration,
+}
+
+impl InfluxReporterBuilder {
+    fn new(url: impl Into<String>) -> InfluxReporterBuilder {
+        InfluxReporterBuilder {
+            url: url.into(),
+            interval: DEFAULT_INTERVAL,
+        }
+    }
+
+    /// Set how often the reporter flushes to InfluxDB. Defaults to 60s.
+    pub fn interval(mut self, interval: Duration) -> InfluxReporterBuilder {
+        self.interval = interval;
+        self
+    }
+
+    /// Start the background flush loop against `registry`.
+    pub fn start(self, registry: Arc<MetricsRegistry>) -> InfluxReporter {
+        let stopped = Arc::new(AtomicBool::new(false));
+        let stopped_for_thread = stopped.clone();
+        let url = self.url;
+        let interval = self.interval;
+
+        let join_handle = thread::spawn(move || {
+            while !stopped_for_thread.load(Ordering::Relaxed) {
+                thread::sleep(interval);
+                if stopped_for_thread.load(Ordering::Relaxed) {
+                    break;
+                }
+
+                let body = to_line_protocol(&registry.snapshots());
+                if !body.is_empty() {
+                    send_with_retry(&url, &body, &stopped_for_thread);
+                }
+            }
+        });
+
+        InfluxReporter {
+            stopped,
+            join_handle: Some(join_handle),
+        }
+    }
+}
+
+/// A running reporter, periodically flushing registry snapshots to
+/// InfluxDB on its own background thread.
+pub struct InfluxReporter {
+    stopped: Arc<AtomicBool>,
+    join_handle: Option<JoinHandle<()>>,
+}
+
+impl InfluxReporter {
+    /// Start building a reporter that writes to the InfluxDB write endpoint
+    /// at `url`.
+    pub fn builder(url: impl Into<String>) -> InfluxReporterBuilder {
+        InfluxReporterBuilder::new(url)
+    }
+
+    /// Stop the flush loop and wait for its background thread to exit.
+    pub fn stop(mut self) {
+        self.stopped.store(true, Ordering::Relaxed);
+        if let Some(join_handle) = self.join_handle.take() {
+            let _ = join_handle.join();
+        }
+    }
+}
+
+/// POST `body` to `url`, retrying with exponential backoff (capped at
+/// `MAX_BACKOFF`) on failure instead of dropping the batch silently.
+fn send_with_retry(url: &str, body: &str, stopped: &AtomicBool) {
+    let mut backoff = INITIAL_BACKOFF;
+
+    loop {
+        if stopped.load(Ordering::Relaxed) {
+            return;
+        }
+
+        match ureq::post(url).send_string(body) {
+            Ok(_) => return,
+            Err(_) => {
+                thread::sleep(backoff);
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+            }
+        }
+    }
+}
+
+/// Escape the characters that are structurally significant in line protocol
+/// for a measurement name (commas and spaces).
+fn escape_measurement(value: &str) -> String {
+    value.replace('\\', "\\\\").replace(',', "\\,").replace(' ', "\\ ")
+}
+
+/// Escape the characters that are structurally significant in line protocol
+/// for a tag key or value (commas, equal signs and spaces).
+fn escape_tag(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(',', "\\,")
+        .replace('=', "\\=")
+        .replace(' ', "\\ ")
+}
+
+fn format_tags(labels: &crate::metrics::Labels) -> String {
+    labels
+        .iter()
+        .map(|(k, v)| format!(",{}={}", escape_tag(k), escape_tag(v)))
+        .collect::<String>()
+}
+
+fn to_line_protocol(snapshots: &HashMap<String, MetricSnapshot>) -> String {
+    let timestamp = clock::system_now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+
+    let mut lines = Vec::new();
+
+    for snapshot in snapshots.values() {
+        if let MetricKind::Timer(timer) = &snapshot.metric {
+            let rate = timer.rate();
+            let latency = timer.latency();
+
+            lines.push(format!(
+                "{measurement}{tags} count={count}i,m1_rate={m1_rate},m5_rate={m5_rate},m15_rate={m15_rate},\
+mean={mean},min={min}i,max={max}i,stddev={stddev},\
+p50={p50},p75={p75},p90={p90},p99={p99},p999={p999} {timestamp}",
+                measurement = escape_measurement(&snapshot.name),
+                tags = format_tags(&snapshot.labels),
+                count = rate.count(),
+                m1_rate = rate.m1_rate(),
+                m5_rate = rate.m5_rate(),
+                m15_rate = rate.m15_rate(),
+                mean = latency.mean(),
+                min = latency.min(),
+                max = latency.max(),
+                stddev = latency.stddev(),
+                p50 = latency.quantile(0.5),
+                p75 = latency.quantile(0.75),
+                p90 = latency.quantile(0.9),
+                p99 = latency.quantile(0.99),
+                p999 = latency.quantile(0.999),
+                timestamp = timestamp,
+            ));
+        }
+    }
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Instant, SystemTime};
+
+    use super::to_line_protocol;
+    use crate::registry::MetricsRegistry;
+
+    #[test]
+    fn test_to_line_protocol_includes_timer_fields() {
+        let registry = MetricsRegistry::new();
+        registry.timer("request", SystemTime::now()).start_at(Instant::now());
+
+        let line = to_line_protocol(&registry.snapshots());
+        assert!(line.starts_with("request count="));
+    }
+
+    #[test]
+    fn test_to_line_protocol_renders_labels_as_tags() {
+        let registry = MetricsRegistry::new();
+        registry
+            .timer_with_labels("request", SystemTime::now(), &[("status", "200")])
+            .start_at(Instant::now());
+
+        let line = to_line_protocol(&registry.snapshots());
+        assert!(line.starts_with("request,status=200 count="));
+    }
+
+    #[test]
+    fn test_to_line_protocol_escapes_special_characters_in_tags() {
+        let registry = MetricsRegistry::new();
+        registry
+            .timer_with_labels("request", SystemTime::now(), &[("path", "a,b=c d")])
+            .start_at(Instant::now());
+
+        let line = to_line_protocol(&registry.snapshots());
+        assert!(line.starts_with("request,path=a\\,b\\=c\\ d count="));
+    }
+}
@@ -0,0 +1,12 @@
+//! metriki-core is a metrics library inspired by Dropwizard Metrics.
+
+pub mod clock;
+pub mod global;
+#[cfg(feature = "http_pull")]
+pub mod http;
+#[cfg(feature = "influx")]
+pub mod influx;
+pub mod metrics;
+pub mod registry;
+
+pub use global::global_registry;
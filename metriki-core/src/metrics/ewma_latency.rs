@@ -0,0 +1,94 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "ser")]
+use serde::ser::SerializeMap;
+#[cfg(feature = "ser")]
+use serde::{Serialize, Serializer};
+
+use crate::clock;
+
+/// A peak exponentially-weighted moving average latency estimator.
+///
+/// Cheap and allocation-free, suitable for recency-weighted latency signals
+/// (e.g. load-balancing decisions) where a full `Timer` histogram is more
+/// than is needed. The estimate decays towards new samples with time
+/// constant `tau`, except that a sample above the current estimate is
+/// applied immediately ("peak" behavior) so spikes aren't smoothed away.
+#[derive(Debug)]
+pub struct EwmaLatency {
+    tau: Duration,
+    estimate: AtomicU64,
+    last_update: Mutex<Instant>,
+}
+
+impl EwmaLatency {
+    pub fn new(tau: Duration) -> EwmaLatency {
+        EwmaLatency {
+            tau,
+            estimate: AtomicU64::new(0f64.to_bits()),
+            last_update: Mutex::new(clock::now()),
+        }
+    }
+
+    /// Record a new latency sample.
+    pub fn record(&self, sample: f64) {
+        let now = clock::now();
+        let mut last_update = self.last_update.lock().unwrap();
+        let elapsed = now.saturating_duration_since(*last_update);
+        *last_update = now;
+
+        let current = f64::from_bits(self.estimate.load(Ordering::Relaxed));
+
+        let next = if current == 0.0 || sample > current {
+            sample
+        } else {
+            let w = (-elapsed.as_secs_f64() / self.tau.as_secs_f64()).exp();
+            current * w + sample * (1.0 - w)
+        };
+
+        self.estimate.store(next.to_bits(), Ordering::Relaxed);
+    }
+
+    /// The current latency estimate.
+    pub fn estimate(&self) -> f64 {
+        f64::from_bits(self.estimate.load(Ordering::Relaxed))
+    }
+}
+
+#[cfg(feature = "ser")]
+impl Serialize for EwmaLatency {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(1))?;
+        map.serialize_entry("estimate", &self.estimate())?;
+        map.end()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::EwmaLatency;
+
+    #[test]
+    fn test_first_sample_sets_estimate_directly() {
+        let ewma = EwmaLatency::new(Duration::from_secs(1));
+        ewma.record(10.0);
+
+        assert_eq!(ewma.estimate(), 10.0);
+    }
+
+    #[test]
+    fn test_spike_is_applied_immediately() {
+        let ewma = EwmaLatency::new(Duration::from_secs(1));
+        ewma.record(10.0);
+        ewma.record(1000.0);
+
+        assert_eq!(ewma.estimate(), 1000.0);
+    }
+}
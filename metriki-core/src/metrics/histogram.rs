@@ -0,0 +1,112 @@
+use std::sync::Mutex;
+
+/// Accumulates `u64` samples and hands out point-in-time snapshots that can
+/// compute quantiles over them.
+#[derive(Debug)]
+pub struct Histogram {
+    values: Mutex<Vec<u64>>,
+}
+
+impl Histogram {
+    pub fn new() -> Histogram {
+        Histogram {
+            values: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Record a new sample.
+    pub fn update(&self, value: u64) {
+        self.values.lock().unwrap().push(value);
+    }
+
+    /// Take an immutable snapshot of all samples recorded so far.
+    pub fn snapshot(&self) -> HistogramSnapshot {
+        HistogramSnapshot::new(self.values.lock().unwrap().clone())
+    }
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Histogram::new()
+    }
+}
+
+/// An immutable, sorted view of the values recorded by a `Histogram` at the
+/// time `snapshot()` was called.
+#[derive(Debug)]
+pub struct HistogramSnapshot {
+    values: Vec<u64>,
+}
+
+impl HistogramSnapshot {
+    pub(crate) fn new(mut values: Vec<u64>) -> HistogramSnapshot {
+        values.sort_unstable();
+        HistogramSnapshot { values }
+    }
+
+    pub fn mean(&self) -> f64 {
+        if self.values.is_empty() {
+            return 0.0;
+        }
+
+        self.values.iter().sum::<u64>() as f64 / self.values.len() as f64
+    }
+
+    pub fn min(&self) -> u64 {
+        self.values.first().copied().unwrap_or(0)
+    }
+
+    pub fn max(&self) -> u64 {
+        self.values.last().copied().unwrap_or(0)
+    }
+
+    pub fn stddev(&self) -> f64 {
+        if self.values.len() < 2 {
+            return 0.0;
+        }
+
+        let mean = self.mean();
+        let variance = self
+            .values
+            .iter()
+            .map(|&v| {
+                let diff = v as f64 - mean;
+                diff * diff
+            })
+            .sum::<f64>()
+            / (self.values.len() - 1) as f64;
+
+        variance.sqrt()
+    }
+
+    /// Returns the value at quantile `q` (0.0..=1.0), e.g. `quantile(0.99)`
+    /// for p99.
+    pub fn quantile(&self, q: f64) -> f64 {
+        if self.values.is_empty() {
+            return 0.0;
+        }
+
+        let pos = ((self.values.len() - 1) as f64 * q).round() as usize;
+        self.values[pos] as f64
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Histogram;
+
+    #[test]
+    fn test_histogram_quantiles() {
+        let histogram = Histogram::new();
+        for i in 1..=100u64 {
+            histogram.update(i);
+        }
+
+        let snapshot = histogram.snapshot();
+        assert_eq!(snapshot.min(), 1);
+        assert_eq!(snapshot.max(), 100);
+        // index = round((100 - 1) * 0.5) = 50, and values[50] is the 51st
+        // smallest value (values are 0-indexed, sorted ascending).
+        assert_eq!(snapshot.quantile(0.5), 51.0);
+    }
+}
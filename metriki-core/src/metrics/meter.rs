@@ -0,0 +1,189 @@
+use std::sync::Mutex;
+use std::time::{Instant, SystemTime};
+
+#[cfg(feature = "ser")]
+use serde::ser::SerializeMap;
+#[cfg(feature = "ser")]
+use serde::{Serialize, Serializer};
+
+use crate::clock;
+
+use super::Labels;
+
+const TICK_INTERVAL_SECONDS: f64 = 5.0;
+
+/// Exponentially-weighted moving average over a sliding window, ticked at a
+/// fixed interval, matching the behavior of Dropwizard's `EWMA`.
+#[derive(Debug)]
+struct Ewma {
+    alpha: f64,
+    rate: Option<f64>,
+}
+
+impl Ewma {
+    fn new(window_minutes: f64) -> Ewma {
+        let alpha = 1.0 - (-TICK_INTERVAL_SECONDS / 60.0 / window_minutes).exp();
+        Ewma { alpha, rate: None }
+    }
+
+    fn update(&mut self, instant_rate: f64) {
+        self.rate = Some(match self.rate {
+            Some(rate) => rate + self.alpha * (instant_rate - rate),
+            None => instant_rate,
+        });
+    }
+
+    fn rate(&self) -> f64 {
+        self.rate.unwrap_or(0.0)
+    }
+}
+
+#[derive(Debug)]
+struct MeterState {
+    count: u64,
+    uncounted: u64,
+    last_tick: Instant,
+    m1: Ewma,
+    m5: Ewma,
+    m15: Ewma,
+}
+
+impl MeterState {
+    fn tick(&mut self) {
+        let elapsed = clock::now() - self.last_tick;
+        let ticks = (elapsed.as_secs_f64() / TICK_INTERVAL_SECONDS).floor();
+
+        if ticks < 1.0 {
+            return;
+        }
+
+        let mut instant_rate = self.uncounted as f64 / TICK_INTERVAL_SECONDS;
+        self.uncounted = 0;
+
+        // Uncounted events belong to the first elapsed tick; any further
+        // ticks saw no events.
+        for _ in 0..(ticks as u64) {
+            self.m1.update(instant_rate);
+            self.m5.update(instant_rate);
+            self.m15.update(instant_rate);
+            instant_rate = 0.0;
+        }
+
+        self.last_tick += std::time::Duration::from_secs_f64(ticks * TICK_INTERVAL_SECONDS);
+    }
+}
+
+/// Tracks the rate of events over 1/5/15 minute moving windows, in the
+/// style of a Dropwizard `Meter`.
+#[derive(Debug)]
+pub struct Meter {
+    start_time: SystemTime,
+    labels: Labels,
+    state: Mutex<MeterState>,
+}
+
+impl Meter {
+    pub fn new(start_time: SystemTime) -> Meter {
+        Meter::with_labels(start_time, Labels::new())
+    }
+
+    /// Create a meter carrying the given labels, e.g. `endpoint=/login`.
+    pub(crate) fn with_labels(start_time: SystemTime, labels: Labels) -> Meter {
+        Meter {
+            start_time,
+            labels,
+            state: Mutex::new(MeterState {
+                count: 0,
+                uncounted: 0,
+                last_tick: clock::now(),
+                m1: Ewma::new(1.0),
+                m5: Ewma::new(5.0),
+                m15: Ewma::new(15.0),
+            }),
+        }
+    }
+
+    /// The labels attached to this meter.
+    pub fn labels(&self) -> &Labels {
+        &self.labels
+    }
+
+    /// Record one event. The `at` argument is not used: the event is counted
+    /// against the current `clock::now()`.
+    pub fn mark(&self, _at: Instant) {
+        let mut state = self.state.lock().unwrap();
+        state.tick();
+        state.count += 1;
+        state.uncounted += 1;
+    }
+
+    pub fn count(&self) -> u64 {
+        self.state.lock().unwrap().count
+    }
+
+    pub fn m1_rate(&self) -> f64 {
+        let mut state = self.state.lock().unwrap();
+        state.tick();
+        state.m1.rate()
+    }
+
+    pub fn m5_rate(&self) -> f64 {
+        let mut state = self.state.lock().unwrap();
+        state.tick();
+        state.m5.rate()
+    }
+
+    pub fn m15_rate(&self) -> f64 {
+        let mut state = self.state.lock().unwrap();
+        state.tick();
+        state.m15.rate()
+    }
+
+    pub fn start_time(&self) -> SystemTime {
+        self.start_time
+    }
+}
+
+#[cfg(feature = "ser")]
+impl Serialize for Meter {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(5))?;
+
+        map.serialize_entry("count", &self.count())?;
+        map.serialize_entry("m1_rate", &self.m1_rate())?;
+        map.serialize_entry("m5_rate", &self.m5_rate())?;
+        map.serialize_entry("m15_rate", &self.m15_rate())?;
+        map.serialize_entry("labels", &self.labels)?;
+
+        map.end()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Instant, SystemTime};
+
+    use super::Meter;
+
+    #[test]
+    fn test_meter_count() {
+        let meter = Meter::new(SystemTime::now());
+        meter.mark(Instant::now());
+        meter.mark(Instant::now());
+
+        assert_eq!(meter.count(), 2);
+    }
+
+    #[test]
+    fn test_meter_with_labels_keeps_labels() {
+        use super::super::labels_from_pairs;
+
+        let labels = labels_from_pairs(&[("endpoint", "/login")]);
+        let meter = Meter::with_labels(SystemTime::now(), labels);
+
+        assert_eq!(meter.labels().get("endpoint").map(String::as_str), Some("/login"));
+    }
+}
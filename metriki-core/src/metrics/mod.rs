@@ -0,0 +1,22 @@
+mod ewma_latency;
+mod histogram;
+mod meter;
+mod timer;
+mod windowed_histogram;
+
+use std::collections::BTreeMap;
+
+pub use ewma_latency::EwmaLatency;
+pub use histogram::{Histogram, HistogramSnapshot};
+pub use meter::Meter;
+pub use timer::{TimeUnit, Timer, TimerContext, TimerContextArc};
+pub use windowed_histogram::WindowedHistogram;
+
+/// Key-value labels (a.k.a. tags) attached to a metric, e.g. `endpoint` or
+/// `status`. Kept sorted so label sets compare and hash consistently
+/// regardless of the order they were passed in.
+pub type Labels = BTreeMap<String, String>;
+
+pub(crate) fn labels_from_pairs(pairs: &[(&str, &str)]) -> Labels {
+    pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+}
@@ -1,12 +1,72 @@
 use std::sync::Arc;
-use std::time::{Instant, SystemTime};
+use std::time::{Duration, Instant, SystemTime};
 
 #[cfg(feature = "ser")]
 use serde::ser::SerializeMap;
 #[cfg(feature = "ser")]
 use serde::{Serialize, Serializer};
 
-use super::{Histogram, HistogramSnapshot, Meter};
+use crate::clock;
+
+use super::{Histogram, HistogramSnapshot, Labels, Meter, WindowedHistogram};
+
+/// The resolution latency is recorded and reported in.
+///
+/// Defaults to `Millis` to match the original behavior, but fast code paths
+/// that complete in well under a millisecond should use a finer unit so
+/// their latency doesn't get truncated to zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimeUnit {
+    Nanos,
+    Micros,
+    #[default]
+    Millis,
+    Seconds,
+}
+
+impl TimeUnit {
+    fn elapsed_as(self, elapsed: Duration) -> u64 {
+        match self {
+            TimeUnit::Nanos => elapsed.as_nanos() as u64,
+            TimeUnit::Micros => elapsed.as_micros() as u64,
+            TimeUnit::Millis => elapsed.as_millis() as u64,
+            TimeUnit::Seconds => elapsed.as_secs(),
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            TimeUnit::Nanos => "nanos",
+            TimeUnit::Micros => "micros",
+            TimeUnit::Millis => "millis",
+            TimeUnit::Seconds => "seconds",
+        }
+    }
+}
+
+/// Storage backing `Timer`'s latency tracking: either a lifetime-long
+/// `Histogram`, or a `WindowedHistogram` that only reflects recent samples.
+#[derive(Debug)]
+enum LatencyStore {
+    Full(Histogram),
+    Windowed(WindowedHistogram),
+}
+
+impl LatencyStore {
+    fn update(&self, value: u64) {
+        match self {
+            LatencyStore::Full(histogram) => histogram.update(value),
+            LatencyStore::Windowed(histogram) => histogram.update(value),
+        }
+    }
+
+    fn snapshot(&self) -> HistogramSnapshot {
+        match self {
+            LatencyStore::Full(histogram) => histogram.snapshot(),
+            LatencyStore::Windowed(histogram) => histogram.snapshot(),
+        }
+    }
+}
 
 /// Timers are combination of `Histogram` and `Meter`.
 ///
@@ -14,7 +74,8 @@ use super::{Histogram, HistogramSnapshot, Meter};
 #[derive(Debug)]
 pub struct Timer {
     rate: Meter,
-    latency: Histogram,
+    latency: LatencyStore,
+    unit: TimeUnit,
 }
 
 #[derive(Debug)]
@@ -38,43 +99,69 @@ pub struct TimerContextArc {
 impl TimerContextArc {
     /// Start the TimerContext from a `Arc` reference of `Timer`.
     pub fn start(timer: Arc<Timer>) -> TimerContextArc {
-        TimerContextArc::start_at(timer, Instant::now())
+        TimerContextArc::start_at(timer, clock::now())
     }
 
     /// Start a timer context for recording that started at given time.
     /// The returned `TimerContext` can be stopped or dropped to record its timing.
     pub fn start_at(timer: Arc<Timer>, start_at: Instant) -> TimerContextArc {
-        timer.rate.mark(Instant::now());
+        timer.rate.mark(clock::now());
         TimerContextArc { start_at, timer }
     }
 
     /// Stop the timer context.
     pub fn stop(&self) {
-        let elapsed = Instant::now() - self.start_at;
-        let elapsed_ms = elapsed.as_millis();
+        let elapsed = clock::now() - self.start_at;
 
-        self.timer.latency.update(elapsed_ms as u64);
+        self.timer.latency.update(self.timer.unit.elapsed_as(elapsed));
     }
 }
 
 impl Timer {
     pub(crate) fn new(start_time: SystemTime) -> Timer {
+        Timer::with_unit(start_time, TimeUnit::default())
+    }
+
+    /// Create a timer that records latency in the given `TimeUnit` instead
+    /// of the default milliseconds.
+    pub(crate) fn with_unit(start_time: SystemTime, unit: TimeUnit) -> Timer {
         Timer {
             rate: Meter::new(start_time),
-            latency: Histogram::new(),
+            latency: LatencyStore::Full(Histogram::new()),
+            unit,
+        }
+    }
+
+    /// Create a timer whose latency snapshot only reflects the last
+    /// `window_size` recorded samples, rather than the whole process
+    /// lifetime.
+    pub(crate) fn with_window(start_time: SystemTime, window_size: usize) -> Timer {
+        Timer {
+            rate: Meter::new(start_time),
+            latency: LatencyStore::Windowed(WindowedHistogram::new(window_size)),
+            unit: TimeUnit::default(),
+        }
+    }
+
+    /// Create a timer carrying the given labels, e.g. `endpoint=/login`.
+    pub(crate) fn with_labels(start_time: SystemTime, labels: Labels) -> Timer {
+        Timer {
+            rate: Meter::with_labels(start_time, labels),
+            latency: LatencyStore::Full(Histogram::new()),
+            unit: TimeUnit::default(),
         }
     }
 
     /// Start a timer context for recording.
     /// The returned `TimerContext` can be stopped or dropped to record its timing.
     pub fn start(&self) -> TimerContext {
-        self.start_at(Instant::now())
+        self.start_at(clock::now())
     }
 
     /// Start a timer context for recording that started at given time.
     /// The returned `TimerContext` can be stopped or dropped to record its timing.
     pub fn start_at(&self, start_at: Instant) -> TimerContext {
-        self.rate.mark(Instant::now());
+        self.rate.mark(clock::now());
         TimerContext {
             start_at,
             timer: self,
@@ -105,10 +192,9 @@ impl Timer {
 
 impl<'a> TimerContext<'a> {
     pub fn stop(&self) {
-        let elapsed = Instant::now() - self.start_at;
-        let elapsed_ms = elapsed.as_millis();
+        let elapsed = clock::now() - self.start_at;
 
-        self.timer.latency.update(elapsed_ms as u64);
+        self.timer.latency.update(self.timer.unit.elapsed_as(elapsed));
     }
 }
 
@@ -124,11 +210,13 @@ impl Serialize for Timer {
     where
         S: Serializer,
     {
-        let mut map = serializer.serialize_map(Some(13))?;
+        let mut map = serializer.serialize_map(Some(15))?;
 
         let rate = self.rate();
         let latency = self.latency();
 
+        map.serialize_entry("labels", rate.labels())?;
+        map.serialize_entry("unit", self.unit.as_str())?;
         map.serialize_entry("count", &rate.count())?;
         map.serialize_entry("m1_rate", &rate.m1_rate())?;
         map.serialize_entry("m5_rate", &rate.m5_rate())?;
@@ -181,4 +269,33 @@ mod test {
         });
         assert!(timer.rate().count() == 1);
     }
+
+    #[test]
+    fn test_timer_with_unit_records_sub_millis_latency() {
+        use super::TimeUnit;
+
+        let timer = Timer::with_unit(SystemTime::now(), TimeUnit::Micros);
+        timer.scoped(|| {
+            #[cfg(feature = "mock_clock")]
+            crate::clock::advance(Duration::from_micros(500));
+            #[cfg(not(feature = "mock_clock"))]
+            std::thread::sleep(Duration::from_micros(500));
+        });
+
+        assert!(timer.latency().max() >= 500);
+    }
+
+    #[test]
+    fn test_timer_with_window_only_keeps_recent_samples() {
+        let timer = Timer::with_window(SystemTime::now(), 5);
+
+        // Sample i is started i ms before now, so it records at least i ms.
+        for i in 0..20u64 {
+            timer.start_at(crate::clock::now() - Duration::from_millis(i));
+        }
+
+        assert_eq!(timer.rate().count(), 20);
+        // Only the last 5 samples (started 15..=19 ms ago) are kept.
+        assert!(timer.latency().min() >= 15);
+    }
 }
@@ -0,0 +1,84 @@
+use std::sync::Mutex;
+
+use super::histogram::HistogramSnapshot;
+
+/// A `Histogram`-like store that only remembers the last `window_size`
+/// samples, so its snapshot reflects recent behavior instead of drifting
+/// over the whole process lifetime.
+#[derive(Debug)]
+pub struct WindowedHistogram {
+    window_size: usize,
+    ring: Mutex<Ring>,
+}
+
+#[derive(Debug)]
+struct Ring {
+    values: Vec<u64>,
+    cursor: usize,
+}
+
+impl WindowedHistogram {
+    /// Create a windowed histogram remembering the last `window_size`
+    /// samples. `window_size` is clamped to at least 1 — a ring buffer of
+    /// capacity 0 can't hold anything, so rather than silently falling back
+    /// to an unbounded histogram we keep the single most recent sample.
+    pub fn new(window_size: usize) -> WindowedHistogram {
+        let window_size = window_size.max(1);
+
+        WindowedHistogram {
+            window_size,
+            ring: Mutex::new(Ring {
+                values: Vec::with_capacity(window_size),
+                cursor: 0,
+            }),
+        }
+    }
+
+    pub fn update(&self, value: u64) {
+        let mut ring = self.ring.lock().unwrap();
+        let window_size = self.window_size;
+
+        if ring.values.len() < window_size {
+            ring.values.push(value);
+        } else {
+            let cursor = ring.cursor % window_size;
+            ring.values[cursor] = value;
+        }
+
+        ring.cursor = ring.cursor.wrapping_add(1);
+    }
+
+    pub fn snapshot(&self) -> HistogramSnapshot {
+        let ring = self.ring.lock().unwrap();
+        HistogramSnapshot::new(ring.values.clone())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::WindowedHistogram;
+
+    #[test]
+    fn test_window_only_keeps_last_n_samples() {
+        let histogram = WindowedHistogram::new(10);
+        for i in 1..=100u64 {
+            histogram.update(i);
+        }
+
+        let snapshot = histogram.snapshot();
+        assert_eq!(snapshot.min(), 91);
+        assert_eq!(snapshot.max(), 100);
+    }
+
+    #[test]
+    fn test_zero_window_size_is_clamped_to_one() {
+        let histogram = WindowedHistogram::new(0);
+        for i in 1..=100u64 {
+            histogram.update(i);
+        }
+
+        let snapshot = histogram.snapshot();
+        assert_eq!(snapshot.min(), 100);
+        assert_eq!(snapshot.max(), 100);
+    }
+}
@@ -0,0 +1,232 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+#[cfg(feature = "ser")]
+use serde::{Serialize, Serializer};
+
+use crate::metrics::{labels_from_pairs, EwmaLatency, Labels, Meter, TimeUnit, Timer};
+
+/// Central store of named metrics.
+///
+/// Metrics are created lazily: the first call to `meter`/`timer` for a given
+/// name (and label set) creates and registers it, subsequent calls with the
+/// same name and labels return the same instance. Metrics are keyed on
+/// `(name, labels)` so the same logical metric name can be recorded across
+/// dimensions (e.g. `endpoint`, `status`) without clashing.
+#[derive(Debug, Default)]
+pub struct MetricsRegistry {
+    meters: Mutex<HashMap<(String, Labels), Arc<Meter>>>,
+    timers: Mutex<HashMap<(String, Labels), Arc<Timer>>>,
+    ewma_latencies: Mutex<HashMap<String, Arc<EwmaLatency>>>,
+}
+
+impl MetricsRegistry {
+    pub fn new() -> MetricsRegistry {
+        MetricsRegistry::default()
+    }
+
+    pub fn arc() -> Arc<MetricsRegistry> {
+        Arc::new(MetricsRegistry::new())
+    }
+
+    /// Look up the `Meter` registered under `name`, creating it (started at
+    /// `start_time`) if it doesn't exist yet.
+    pub fn meter(&self, name: &str, start_time: SystemTime) -> Arc<Meter> {
+        self.meter_with_labels(name, start_time, &[])
+    }
+
+    /// Look up the `Meter` registered under `name` with the given `labels`,
+    /// creating it (started at `start_time`) if it doesn't exist yet.
+    pub fn meter_with_labels(&self, name: &str, start_time: SystemTime, labels: &[(&str, &str)]) -> Arc<Meter> {
+        let labels = labels_from_pairs(labels);
+        let mut meters = self.meters.lock().unwrap();
+        meters
+            .entry((name.to_string(), labels.clone()))
+            .or_insert_with(|| Arc::new(Meter::with_labels(start_time, labels)))
+            .clone()
+    }
+
+    /// Look up the `Timer` registered under `name`, creating it (started at
+    /// `start_time`) if it doesn't exist yet.
+    pub fn timer(&self, name: &str, start_time: SystemTime) -> Arc<Timer> {
+        let mut timers = self.timers.lock().unwrap();
+        timers
+            .entry((name.to_string(), Labels::new()))
+            .or_insert_with(|| Arc::new(Timer::new(start_time)))
+            .clone()
+    }
+
+    /// Look up the `Timer` registered under `name` with the given `labels`,
+    /// creating it (started at `start_time`) if it doesn't exist yet.
+    pub fn timer_with_labels(&self, name: &str, start_time: SystemTime, labels: &[(&str, &str)]) -> Arc<Timer> {
+        let labels = labels_from_pairs(labels);
+        let mut timers = self.timers.lock().unwrap();
+        timers
+            .entry((name.to_string(), labels.clone()))
+            .or_insert_with(|| Arc::new(Timer::with_labels(start_time, labels)))
+            .clone()
+    }
+
+    /// Look up the `Timer` registered under `name`, creating it (started at
+    /// `start_time`, recording latency in `unit`) if it doesn't exist yet.
+    pub fn timer_with_unit(&self, name: &str, start_time: SystemTime, unit: TimeUnit) -> Arc<Timer> {
+        let mut timers = self.timers.lock().unwrap();
+        timers
+            .entry((name.to_string(), Labels::new()))
+            .or_insert_with(|| Arc::new(Timer::with_unit(start_time, unit)))
+            .clone()
+    }
+
+    /// Look up the `Timer` registered under `name`, creating it (started at
+    /// `start_time`, keeping only the last `window_size` latency samples) if
+    /// it doesn't exist yet.
+    pub fn timer_with_window(&self, name: &str, start_time: SystemTime, window_size: usize) -> Arc<Timer> {
+        let mut timers = self.timers.lock().unwrap();
+        timers
+            .entry((name.to_string(), Labels::new()))
+            .or_insert_with(|| Arc::new(Timer::with_window(start_time, window_size)))
+            .clone()
+    }
+
+    /// Look up the `EwmaLatency` registered under `name`, creating it (with
+    /// decay time-constant `tau`) if it doesn't exist yet.
+    pub fn ewma_latency(&self, name: &str, tau: Duration) -> Arc<EwmaLatency> {
+        let mut ewma_latencies = self.ewma_latencies.lock().unwrap();
+        ewma_latencies
+            .entry(name.to_string())
+            .or_insert_with(|| Arc::new(EwmaLatency::new(tau)))
+            .clone()
+    }
+
+    /// Snapshot every registered metric, keyed by name (with any labels
+    /// appended so distinct label sets for the same name don't collide).
+    /// Each snapshot also carries its name and labels unencoded, so
+    /// reporters can render them properly instead of parsing the map key.
+    pub fn snapshots(&self) -> HashMap<String, MetricSnapshot> {
+        let mut snapshots = HashMap::new();
+
+        for ((name, labels), meter) in self.meters.lock().unwrap().iter() {
+            snapshots.insert(
+                snapshot_key(name, labels),
+                MetricSnapshot {
+                    name: name.clone(),
+                    labels: labels.clone(),
+                    metric: MetricKind::Meter(meter.clone()),
+                },
+            );
+        }
+
+        for ((name, labels), timer) in self.timers.lock().unwrap().iter() {
+            snapshots.insert(
+                snapshot_key(name, labels),
+                MetricSnapshot {
+                    name: name.clone(),
+                    labels: labels.clone(),
+                    metric: MetricKind::Timer(timer.clone()),
+                },
+            );
+        }
+
+        for (name, ewma_latency) in self.ewma_latencies.lock().unwrap().iter() {
+            snapshots.insert(
+                name.clone(),
+                MetricSnapshot {
+                    name: name.clone(),
+                    labels: Labels::new(),
+                    metric: MetricKind::EwmaLatency(ewma_latency.clone()),
+                },
+            );
+        }
+
+        snapshots
+    }
+}
+
+fn snapshot_key(name: &str, labels: &Labels) -> String {
+    if labels.is_empty() {
+        return name.to_string();
+    }
+
+    let tags = labels
+        .iter()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect::<Vec<_>>()
+        .join(",");
+
+    format!("{}{{{}}}", name, tags)
+}
+
+/// A single registered metric, returned from `MetricsRegistry::snapshots`.
+///
+/// `name` and `labels` are kept as plain, unescaped data here so reporters
+/// (Prometheus, InfluxDB, ...) can render them in whatever format they need,
+/// rather than re-parsing them out of a formatted map key.
+#[derive(Debug)]
+pub struct MetricSnapshot {
+    pub name: String,
+    pub labels: Labels,
+    pub metric: MetricKind,
+}
+
+/// The underlying metric backing a `MetricSnapshot`.
+#[derive(Debug)]
+pub enum MetricKind {
+    Meter(Arc<Meter>),
+    Timer(Arc<Timer>),
+    EwmaLatency(Arc<EwmaLatency>),
+}
+
+#[cfg(feature = "ser")]
+impl Serialize for MetricSnapshot {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match &self.metric {
+            MetricKind::Meter(meter) => meter.serialize(serializer),
+            MetricKind::Timer(timer) => timer.serialize(serializer),
+            MetricKind::EwmaLatency(ewma_latency) => ewma_latency.serialize(serializer),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Instant, SystemTime};
+
+    use super::MetricsRegistry;
+
+    #[test]
+    fn test_meter_is_shared_across_lookups() {
+        let registry = MetricsRegistry::new();
+        registry.meter("hello", SystemTime::now()).mark(Instant::now());
+        registry.meter("hello", SystemTime::now()).mark(Instant::now());
+
+        assert_eq!(registry.meter("hello", SystemTime::now()).count(), 2);
+    }
+
+    #[test]
+    fn test_distinct_label_sets_are_distinct_metrics() {
+        let registry = MetricsRegistry::new();
+        registry
+            .meter_with_labels("requests", SystemTime::now(), &[("status", "200")])
+            .mark(Instant::now());
+        registry
+            .meter_with_labels("requests", SystemTime::now(), &[("status", "500")])
+            .mark(Instant::now());
+
+        assert_eq!(
+            registry
+                .meter_with_labels("requests", SystemTime::now(), &[("status", "200")])
+                .count(),
+            1
+        );
+        assert_eq!(
+            registry
+                .meter_with_labels("requests", SystemTime::now(), &[("status", "500")])
+                .count(),
+            1
+        );
+    }
+}